@@ -8,15 +8,95 @@ pub enum Side {
     Sell,
 }
 
+impl Side {
+    /// Returns the opposite side of the book.
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+/// Reasons an order can be rejected at entry before any matching occurs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderError {
+    /// Price is not a multiple of the book's tick size.
+    InvalidTick,
+    /// Quantity is not a multiple of the book's lot size.
+    InvalidLot,
+    /// Quantity is below the book's minimum order size.
+    BelowMinimum,
+}
+
+/// How an incoming order is allowed to interact with the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    /// Match against the opposite side, resting any remainder.
+    Limit,
+    /// Match with an implicit worst-case limit and never rest.
+    Market,
+    /// Reject outright if it would cross the opposite side.
+    PostOnly,
+    /// Re-price one tick inside the opposite best instead of crossing.
+    PostOnlySlide,
+}
+
 #[derive(Debug, Clone)]
 pub struct Order {
     pub id: u64,
     pub price: u64,
     pub quantity: u64,
     pub timestamp: u128,
+    /// Wall-clock (millis since epoch) at which the order expires, if any.
+    pub expiry_ts: Option<u128>,
+    /// Participant/account that owns the order, used for self-trade prevention.
+    pub owner: u64,
 }
 
+/// Policy controlling what happens when an incoming order would match a resting
+/// order owned by the same participant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// Allow the self-match to trade as usual (no prevention).
+    Allow,
+    /// Discard the resting maker and keep matching the taker.
+    CancelResting,
+    /// Stop matching and drop the taker's remainder.
+    CancelTaker,
+    /// Decrement the overlapping quantity off both without a trade and cancel
+    /// the smaller.
+    DecrementAndCancel,
+}
+
+/// How long an order remains active once placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// Good-till-cancelled: rest any remainder indefinitely.
+    Gtc,
+    /// Immediate-or-cancel: match what is possible and discard the remainder.
+    Ioc,
+    /// Good-till-date: rest until the given expiry timestamp (millis since epoch).
+    Gtd(u128),
+}
+
+/// A resting order whose effective price tracks an external reference price.
+///
+/// The effective price is `oracle_price + offset`, optionally bounded by
+/// `peg_limit` (a worst-case price). While the peg cannot be resolved — no
+/// oracle price is set, the result would be non-positive, or it would breach
+/// `peg_limit` — the order is temporarily invalid and takes no part in matching.
 #[derive(Debug, Clone)]
+pub struct PeggedOrder {
+    /// The underlying order; its `price` field is unused while pegged.
+    pub order: Order,
+    /// Signed offset applied to the oracle price.
+    pub offset: i64,
+    /// Worst-case price the peg is allowed to reach, if any.
+    pub peg_limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Trade {
     pub price: u64,
     pub quantity: u64,
@@ -63,5 +143,5 @@ impl Ord for PriceLevel {
 
 
 pub mod prelude {
-    pub use crate::{Order, Trade, Side, PriceLevel};
+    pub use crate::{Order, OrderError, OrderType, PeggedOrder, SelfTradePrevention, TimeInForce, Trade, Side, PriceLevel};
 }
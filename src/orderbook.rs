@@ -1,7 +1,20 @@
-use std::{collections::{BTreeMap, VecDeque}, time::{SystemTime, UNIX_EPOCH}};
+use std::{collections::{BTreeMap, HashMap, VecDeque}, time::{SystemTime, UNIX_EPOCH}};
 
 use crate::prelude::*;
 
+/// Maximum number of expired orders a single `place_order` call may reap while
+/// matching, bounding incidental cleanup work per incoming order.
+const DROP_EXPIRED_ORDER_LIMIT: usize = 5;
+
+/// Identifies a resting maker across the fixed price levels and the pegged lists.
+#[derive(Debug, Clone, Copy)]
+enum Maker {
+    /// The front order of the given fixed price level.
+    Fixed(PriceLevel),
+    /// The pegged order at the given index on the given side.
+    Pegged(Side, usize),
+}
+
 /// An order book that matches buy and sell orders based on price-time priority.
 #[derive(Default)]
 pub struct OrderBook {
@@ -9,153 +22,505 @@ pub struct OrderBook {
     buy_side: BTreeMap<PriceLevel, VecDeque<Order>>,
     /// Sell side orders, lowest priority first.
     sell_side: BTreeMap<PriceLevel, VecDeque<Order>>,
+    /// Maps each resting order id to its price level so it can be located
+    /// without scanning every `VecDeque`: cancellation is an O(log n) level
+    /// lookup plus a scan of that one level's queue.
+    order_index: HashMap<u64, PriceLevel>,
+    /// Price grid: incoming prices must be a multiple of this (0 disables the check).
+    tick_size: u64,
+    /// Quantity grid: incoming quantities must be a multiple of this (0 disables the check).
+    lot_size: u64,
+    /// Smallest accepted order quantity (0 disables the check).
+    min_size: u64,
+    /// External reference price used to resolve pegged orders.
+    oracle_price: Option<u64>,
+    /// Pegged buy orders, in placement (time priority) order.
+    pegged_buy_side: Vec<PeggedOrder>,
+    /// Pegged sell orders, in placement (time priority) order.
+    pegged_sell_side: Vec<PeggedOrder>,
+}
+
+/// Current wall-clock time in milliseconds since the Unix epoch.
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backward")
+        .as_millis()
+}
+
+/// Resolves the effective price of a pegged order on `side` against `oracle`.
+///
+/// Returns `None` when the peg is currently invalid — a non-positive effective
+/// price, or an effective price that would breach the order's `peg_limit` — in
+/// which case the order is temporarily invalid and takes no part in matching
+/// (it is skipped, not removed).
+fn pegged_price(side: Side, oracle: u64, offset: i64, peg_limit: Option<u64>) -> Option<u64> {
+    let raw = oracle as i128 + offset as i128;
+    if raw < 1 {
+        return None;
+    }
+    let eff = raw as u64;
+    if let Some(limit) = peg_limit {
+        let breaches = match side {
+            // A buy's worst case is paying too much.
+            Side::Buy => eff > limit,
+            // A sell's worst case is receiving too little.
+            Side::Sell => eff < limit,
+        };
+        if breaches {
+            return None;
+        }
+    }
+    Some(eff)
+}
+
+/// Returns true if a maker priced `a` outranks one priced `b` on `side` under
+/// price-time priority (better price first, earlier timestamp breaking ties).
+fn outranks(side: Side, a: (u64, u128), b: (u64, u128)) -> bool {
+    let (a_price, a_ts) = a;
+    let (b_price, b_ts) = b;
+    let price_better = match side {
+        Side::Buy => a_price > b_price,
+        Side::Sell => a_price < b_price,
+    };
+    price_better || (a_price == b_price && a_ts < b_ts)
 }
 
 impl OrderBook {
-    /// Places a new order into the order book.
+    /// Creates an order book constrained to the given price/quantity grid.
+    ///
+    /// A zero `tick_size` or `lot_size` disables the respective divisibility
+    /// check; a zero `min_size` accepts any quantity.
+    pub fn new(tick_size: u64, lot_size: u64, min_size: u64) -> Self {
+        Self {
+            tick_size,
+            lot_size,
+            min_size,
+            ..Default::default()
+        }
+    }
+
+    /// Places a new limit order into the order book.
     ///
     /// - Attempts to match the incoming order against the opposite side of the book.
     /// - Executes trades until the order is either fully matched or no more matches are possible.
     /// - Any unfilled remainder is added to the appropriate side of the book.
     ///
+    /// Returns a list of all trades generated from this order, or an
+    /// [`OrderError`] if the order fails grid/size validation.
+    pub fn place_order(&mut self, side: Side, price: u64, quantity: u64, id: u64) -> Result<Vec<Trade>, OrderError> {
+        self.place(side, price, quantity, id, OrderType::Limit, TimeInForce::Gtc)
+    }
+
+    /// Places a new order of the given [`OrderType`] into the book.
+    ///
+    /// - A **Market** order matches with an implicit worst-case limit and never rests;
+    ///   any unfilled remainder is discarded.
+    /// - A **PostOnly** order that would cross the opposite side is rejected, returning
+    ///   an empty trade set.
+    /// - A **PostOnlySlide** order that would cross is re-priced one tick inside the
+    ///   opposite best so it rests as a maker instead of crossing.
+    ///
+    /// Returns a list of all trades generated from this order, or an
+    /// [`OrderError`] if the order fails grid/size validation.
+    ///
+    /// The order is owned by `id` and no self-trade prevention is applied; use
+    /// [`OrderBook::place_full`] to control the owner and prevention policy.
+    pub fn place(&mut self, side: Side, price: u64, quantity: u64, id: u64, order_type: OrderType, tif: TimeInForce) -> Result<Vec<Trade>, OrderError> {
+        self.place_full(side, price, quantity, id, id, order_type, tif, SelfTradePrevention::Allow)
+    }
+
+    /// Places a new order with full control over the owner and self-trade
+    /// prevention policy, in addition to the [`OrderType`] and [`TimeInForce`].
+    ///
+    /// When the taker would match a resting order owned by `owner`, `stp`
+    /// decides the outcome per fill (see [`SelfTradePrevention`]).
+    ///
+    /// Returns a list of all trades generated from this order, or an
+    /// [`OrderError`] if the order fails grid/size validation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_full(&mut self, side: Side, price: u64, quantity: u64, id: u64, owner: u64, order_type: OrderType, tif: TimeInForce, stp: SelfTradePrevention) -> Result<Vec<Trade>, OrderError> {
+        self.validate(price, quantity)?;
+
+        // The effective limit used for matching; Market orders use an implicit bound.
+        let mut limit = match order_type {
+            OrderType::Market => match side {
+                Side::Buy => u64::MAX,
+                Side::Sell => 1,
+            },
+            _ => price,
+        };
+
+        // Maker-only order types must not cross the opposite best.
+        match order_type {
+            OrderType::PostOnly if self.would_cross(side, limit) => {
+                return Ok(Vec::new());
+            }
+            OrderType::PostOnlySlide => {
+                if let Some(slid) = self.slide_price(side, limit) {
+                    limit = slid;
+                }
+            }
+            _ => {}
+        }
+
+        // Market and IOC orders never rest their remainder.
+        let rest = !matches!(order_type, OrderType::Market)
+            && !matches!(tif, TimeInForce::Ioc);
+        let expiry_ts = match tif {
+            TimeInForce::Gtd(ts) => Some(ts),
+            _ => None,
+        };
+        Ok(self.match_and_rest(side, limit, quantity, id, owner, rest, expiry_ts, stp))
+    }
+
+    /// Validates an incoming order against the book's price/quantity grid.
+    ///
+    /// A zero grid value disables the corresponding check.
+    fn validate(&self, price: u64, quantity: u64) -> Result<(), OrderError> {
+        if self.tick_size > 0 && !price.is_multiple_of(self.tick_size) {
+            return Err(OrderError::InvalidTick);
+        }
+        if self.lot_size > 0 && !quantity.is_multiple_of(self.lot_size) {
+            return Err(OrderError::InvalidLot);
+        }
+        if quantity < self.min_size {
+            return Err(OrderError::BelowMinimum);
+        }
+        Ok(())
+    }
+
+    /// Sets the external reference price used to resolve pegged orders.
+    ///
+    /// Pegged orders reprice against this value at once, but setting it does not
+    /// itself trigger matching: a pegged order that now crosses the opposite side
+    /// trades only when a later incoming order is matched against it.
+    pub fn set_oracle_price(&mut self, price: u64) {
+        self.oracle_price = Some(price);
+    }
+
+    /// Places an oracle-pegged order whose effective price is `oracle + offset`,
+    /// optionally bounded by a worst-case `peg_limit`.
+    ///
+    /// - If the peg currently resolves, the order first matches as a taker at its
+    ///   effective price; any remainder rests in the pegged list and reprices as
+    ///   the oracle moves.
+    /// - While the peg is unresolved (no oracle set, a non-positive price, or a
+    ///   price beyond `peg_limit`) the order simply rests and takes no part in
+    ///   matching until it resolves.
+    ///
+    /// Only the quantity grid applies, as a pegged order has no fixed price.
+    pub fn place_pegged_order(&mut self, side: Side, offset: i64, quantity: u64, id: u64, peg_limit: Option<u64>) -> Result<Vec<Trade>, OrderError> {
+        if self.lot_size > 0 && !quantity.is_multiple_of(self.lot_size) {
+            return Err(OrderError::InvalidLot);
+        }
+        if quantity < self.min_size {
+            return Err(OrderError::BelowMinimum);
+        }
+
+        let now = now_ms();
+        let mut trades = Vec::new();
+        let mut reaped = 0;
+        let mut remaining = quantity;
+
+        // Match as a taker at the current effective price, if the peg resolves.
+        if let Some(oracle) = self.oracle_price {
+            if let Some(eff) = pegged_price(side, oracle, offset, peg_limit) {
+                (remaining, _) = self.match_against(side, eff, quantity, id, id, now, &mut reaped, SelfTradePrevention::Allow, &mut trades);
+            }
+        }
+
+        if remaining > 0 {
+            let pegged = PeggedOrder {
+                order: Order { id, price: 0, quantity: remaining, timestamp: now, expiry_ts: None, owner: id },
+                offset,
+                peg_limit,
+            };
+            self.pegged_mut(side).push(pegged);
+        }
+
+        Ok(trades)
+    }
+
+    /// Matches an incoming order at `limit` against the opposite side, optionally
+    /// resting any unfilled remainder.
+    ///
     /// Returns a list of all trades generated from this order.
-    pub fn place_order(&mut self, side: Side, price: u64, quantity: u64, id: u64) -> Vec<Trade> {
+    #[allow(clippy::too_many_arguments)]
+    fn match_and_rest(&mut self, side: Side, limit: u64, quantity: u64, id: u64, owner: u64, rest: bool, expiry_ts: Option<u128>, stp: SelfTradePrevention) -> Vec<Trade> {
         let mut trades = Vec::new();
+        let now = now_ms();
+        let mut reaped = 0;
 
-        // current timestamp since epoch
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backward")
-            .as_millis();
-
-        let mut incoming_order = Order {
-            id,
-            quantity,
-            price,
-            timestamp: now,
-        };
+        let (remaining, cancelled) = self.match_against(side, limit, quantity, id, owner, now, &mut reaped, stp, &mut trades);
 
-        // Match against opposite side
-        match side {
-            Side::Buy => {
-                // Match against best sell orders
-                while incoming_order.quantity > 0 {
-                    // Get the best sell price level
-                    let best_sell_price = self.sell_side.first_key_value()
-                        .map(|(k, _)| k.price);
-                    
-                    if let Some(best_price) = best_sell_price {
-                        if price >= best_price {
-                            // Can match this buy order with best sell order
-                            incoming_order.quantity = self.match_at_price_level(
-                                Side::Sell,
-                                best_price,
-                                incoming_order.quantity,
-                                id,
-                                &mut trades
-                            );
+        // Add remainder to book if unfilled, allowed to rest, and not cancelled
+        // by self-trade prevention.
+        if rest && remaining > 0 && !cancelled {
+            self.add_to_book(side, Order {
+                id,
+                quantity: remaining,
+                price: limit,
+                timestamp: now,
+                expiry_ts,
+                owner,
+            });
+        }
+
+        trades
+    }
+
+    /// Matches a taker on `side` against the opposite side — considering both
+    /// fixed-price and pegged makers — honouring price-time priority across both.
+    ///
+    /// - Expired makers are skipped and reaped, bounded by [`DROP_EXPIRED_ORDER_LIMIT`]
+    ///   per call; an expired maker never produces a trade.
+    /// - When a maker is owned by `taker_owner`, the `stp` policy decides the
+    ///   outcome for that fill (see [`SelfTradePrevention`]).
+    /// - Stops once the taker is filled, the best maker no longer crosses `limit`,
+    ///   or an unreaped expired maker blocks further progress.
+    ///
+    /// Returns the remaining unfilled quantity and whether the taker was
+    /// cancelled by self-trade prevention (and so must not rest).
+    #[allow(clippy::too_many_arguments)]
+    fn match_against(&mut self, side: Side, limit: u64, mut qty: u64, taker_id: u64, taker_owner: u64, now: u128, reaped: &mut usize, stp: SelfTradePrevention, trades: &mut Vec<Trade>) -> (u64, bool) {
+        let maker_side = side.opposite();
+
+        while qty > 0 {
+            let (eff_price, _, maker) = match self.best_maker(maker_side) {
+                Some(best) => best,
+                None => break,
+            };
+
+            // Stop once the best maker no longer crosses the taker's limit.
+            let crosses = match side {
+                Side::Buy => limit >= eff_price,
+                Side::Sell => limit <= eff_price,
+            };
+            if !crosses {
+                break;
+            }
+
+            // Skip and reap expired makers, bounded per call; never trade one.
+            if self.maker_expiry(maker).is_some_and(|exp| exp <= now) {
+                if *reaped >= DROP_EXPIRED_ORDER_LIMIT {
+                    break;
+                }
+                self.remove_maker(maker);
+                *reaped += 1;
+                continue;
+            }
+
+            let maker_qty = self.maker_quantity(maker);
+
+            // Enforce self-trade prevention when owners collide.
+            if stp != SelfTradePrevention::Allow && self.maker_owner(maker) == taker_owner {
+                match stp {
+                    SelfTradePrevention::CancelResting => {
+                        self.remove_maker(maker);
+                        continue;
+                    }
+                    SelfTradePrevention::CancelTaker => {
+                        return (qty, true);
+                    }
+                    SelfTradePrevention::DecrementAndCancel => {
+                        let decrement = qty.min(maker_qty);
+                        qty -= decrement;
+                        if decrement == maker_qty {
+                            self.remove_maker(maker);
                         } else {
-                            break; // No more matches possible
+                            self.reduce_maker(maker, decrement);
                         }
-                    } else {
-                        break; // No sell orders to match
+                        continue;
                     }
+                    SelfTradePrevention::Allow => unreachable!(),
                 }
             }
-            Side::Sell => {
-                // Match against best buy orders
-                while incoming_order.quantity > 0 {
-                    // Get the best buy price level
-                    let best_buy_price = self.buy_side.first_key_value()
-                        .map(|(k, _)| k.price);
-                    
-                    if let Some(best_price) = best_buy_price {
-                        if price <= best_price {
-                            // Can match this sell order with the best buy order
-                            incoming_order.quantity = self.match_at_price_level(
-                                Side::Buy,
-                                best_price,
-                                incoming_order.quantity,
-                                id,
-                                &mut trades
-                            );
-                        } else {
-                            break; // No more matches possible
-                        }
-                    } else {
-                        break; // No buy orders to match
+
+            let trade_qty = qty.min(maker_qty);
+
+            trades.push(Trade {
+                price: eff_price,
+                quantity: trade_qty,
+                maker_id: self.maker_id(maker),
+                taker_id,
+            });
+
+            qty -= trade_qty;
+            if trade_qty == maker_qty {
+                self.remove_maker(maker);
+            } else {
+                self.reduce_maker(maker, trade_qty);
+            }
+        }
+
+        (qty, false)
+    }
+
+    /// Returns the highest-priority maker on `side` — across fixed and pegged
+    /// orders — as its effective price, timestamp, and a [`Maker`] handle.
+    fn best_maker(&self, side: Side) -> Option<(u64, u128, Maker)> {
+        let fixed = self.fixed_book(side).first_key_value()
+            .and_then(|(key, orders)| orders.front().map(|o| (key.price, o.timestamp, Maker::Fixed(*key))));
+
+        let mut best_pegged: Option<(u64, u128, Maker)> = None;
+        if let Some(oracle) = self.oracle_price {
+            for (i, pegged) in self.pegged(side).iter().enumerate() {
+                if let Some(eff) = pegged_price(side, oracle, pegged.offset, pegged.peg_limit) {
+                    let cand = (eff, pegged.order.timestamp, Maker::Pegged(side, i));
+                    let keep_current = matches!(&best_pegged, Some(cur)
+                        if !outranks(side, (cand.0, cand.1), (cur.0, cur.1)));
+                    if !keep_current {
+                        best_pegged = Some(cand);
                     }
                 }
             }
         }
 
-        // Add remainder to book if unfilled
-        if incoming_order.quantity > 0 {
-            self.add_to_book(side, incoming_order);
+        match (fixed, best_pegged) {
+            (Some(f), Some(p)) => {
+                if outranks(side, (f.0, f.1), (p.0, p.1)) { Some(f) } else { Some(p) }
+            }
+            (Some(f), None) => Some(f),
+            (None, p) => p,
         }
+    }
 
-        trades
+    /// Returns true if an order resting at `limit` on `side` would cross the
+    /// opposite side's best price.
+    fn would_cross(&self, side: Side, limit: u64) -> bool {
+        match self.best_maker(side.opposite()) {
+            Some((best, _, _)) => match side {
+                Side::Buy => limit >= best,
+                Side::Sell => limit <= best,
+            },
+            None => false,
+        }
     }
 
-    /// Attempts to match a taker order at a given price level.
-    ///
-    /// - Iterates through maker orders at this price level (FIFO order).
-    /// - Executes trades until either the taker is fully filled or no makers remain.
-    /// - Removes maker orders that are fully filled.
-    ///
-    /// Returns the remaining unfilled quantity of the taker order.
-    fn match_at_price_level(
-        &mut self,
-        book_side: Side,
-        price: u64,
-        mut qty: u64,
-        taker_id: u64,
-        trades: &mut Vec<Trade>
-    ) -> u64 {
-        let price_key = PriceLevel { price, side: book_side };
-        let book = match book_side {
-            Side::Buy => &mut self.buy_side,
-            Side::Sell => &mut self.sell_side,
-        };
+    /// Re-prices a crossing order one tick inside the opposite best, returning the
+    /// slid limit, or `None` if the order would not cross.
+    fn slide_price(&self, side: Side, limit: u64) -> Option<u64> {
+        let best = self.best_maker(side.opposite()).map(|(price, _, _)| price)?;
+        // A zero tick size disables the price grid, so slide by one price unit.
+        let tick = self.tick_size.max(1);
+        match side {
+            Side::Buy => (limit >= best).then(|| limit.min(best.saturating_sub(tick))),
+            Side::Sell => (limit <= best).then(|| limit.max(best.saturating_add(tick))),
+        }
+    }
 
-        if let Some(orders) = book.get_mut(&price_key) {
-            while qty > 0 && !orders.is_empty() {
-                let front_order = orders.front_mut().unwrap();
-                
-                let trade_qty = qty.min(front_order.quantity);
-                
-                // Create trade between maker and taker
-                trades.push(Trade {
-                    price,
-                    quantity: trade_qty,
-                    maker_id: front_order.id,
-                    taker_id,
-                });
+    /// Immutable view of the fixed book on the given side.
+    fn fixed_book(&self, side: Side) -> &BTreeMap<PriceLevel, VecDeque<Order>> {
+        match side {
+            Side::Buy => &self.buy_side,
+            Side::Sell => &self.sell_side,
+        }
+    }
 
-                // Update quantities
-                qty -= trade_qty;
-                front_order.quantity -= trade_qty;
+    /// Immutable view of the pegged orders on the given side.
+    fn pegged(&self, side: Side) -> &Vec<PeggedOrder> {
+        match side {
+            Side::Buy => &self.pegged_buy_side,
+            Side::Sell => &self.pegged_sell_side,
+        }
+    }
+
+    /// Expiry timestamp of the given maker, if it has one.
+    fn maker_expiry(&self, maker: Maker) -> Option<u128> {
+        match maker {
+            Maker::Fixed(key) => self.fixed_book(key.side).get(&key).and_then(|o| o.front()).and_then(|o| o.expiry_ts),
+            Maker::Pegged(side, i) => self.pegged(side).get(i).and_then(|p| p.order.expiry_ts),
+        }
+    }
+
+    /// Remaining quantity of the given maker.
+    fn maker_quantity(&self, maker: Maker) -> u64 {
+        match maker {
+            Maker::Fixed(key) => self.fixed_book(key.side).get(&key).and_then(|o| o.front()).map_or(0, |o| o.quantity),
+            Maker::Pegged(side, i) => self.pegged(side).get(i).map_or(0, |p| p.order.quantity),
+        }
+    }
+
+    /// Order id of the given maker.
+    fn maker_id(&self, maker: Maker) -> u64 {
+        match maker {
+            Maker::Fixed(key) => self.fixed_book(key.side).get(&key).and_then(|o| o.front()).map_or(0, |o| o.id),
+            Maker::Pegged(side, i) => self.pegged(side).get(i).map_or(0, |p| p.order.id),
+        }
+    }
 
-                // Remove order if fully filled
-                if front_order.quantity == 0 {
-                    orders.pop_front();
+    /// Owning account of the given maker.
+    fn maker_owner(&self, maker: Maker) -> u64 {
+        match maker {
+            Maker::Fixed(key) => self.fixed_book(key.side).get(&key).and_then(|o| o.front()).map_or(0, |o| o.owner),
+            Maker::Pegged(side, i) => self.pegged(side).get(i).map_or(0, |p| p.order.owner),
+        }
+    }
+
+    /// Reduces the given maker's quantity by `by`.
+    fn reduce_maker(&mut self, maker: Maker, by: u64) {
+        match maker {
+            Maker::Fixed(key) => {
+                let book = match key.side {
+                    Side::Buy => &mut self.buy_side,
+                    Side::Sell => &mut self.sell_side,
+                };
+                if let Some(order) = book.get_mut(&key).and_then(|o| o.front_mut()) {
+                    order.quantity -= by;
                 }
             }
+            Maker::Pegged(side, i) => {
+                if let Some(pegged) = self.pegged_mut(side).get_mut(i) {
+                    pegged.order.quantity -= by;
+                }
+            }
+        }
+    }
 
-            // Remove price level if no orders left
-            if orders.is_empty() {
-                book.remove(&price_key);
+    /// Removes the given maker from the book, dropping empty levels and keeping
+    /// the id index in sync.
+    fn remove_maker(&mut self, maker: Maker) {
+        match maker {
+            Maker::Fixed(key) => {
+                let book = match key.side {
+                    Side::Buy => &mut self.buy_side,
+                    Side::Sell => &mut self.sell_side,
+                };
+                let mut removed_id = None;
+                if let Some(orders) = book.get_mut(&key) {
+                    removed_id = orders.pop_front().map(|o| o.id);
+                    if orders.is_empty() {
+                        book.remove(&key);
+                    }
+                }
+                if let Some(id) = removed_id {
+                    self.order_index.remove(&id);
+                }
+            }
+            Maker::Pegged(side, i) => {
+                let pegged = self.pegged_mut(side);
+                if i < pegged.len() {
+                    pegged.remove(i);
+                }
             }
         }
+    }
 
-        qty
+    /// Mutable view of the pegged orders on the given side.
+    fn pegged_mut(&mut self, side: Side) -> &mut Vec<PeggedOrder> {
+        match side {
+            Side::Buy => &mut self.pegged_buy_side,
+            Side::Sell => &mut self.pegged_sell_side,
+        }
     }
 
     /// Adds a new order to the order book at the given price level.
     /// Preserves FIFO order at each price level.
     fn add_to_book(&mut self, side: Side, order: Order) {
         let price_key = PriceLevel { price: order.price, side };
-        
+        self.order_index.insert(order.id, price_key);
+
         match side {
             Side::Buy => {
                 self.buy_side
@@ -173,26 +538,188 @@ impl OrderBook {
     }
 
 
+    /// Cancels a resting order by id, removing it from its price level.
+    ///
+    /// - Uses the order id index to jump straight to the owning price level.
+    /// - Drops the price level entirely if it becomes empty.
+    ///
+    /// Returns the cancelled order, or `None` if no such resting order exists.
+    /// Pegged orders are not in the id index, so they cannot be cancelled here.
+    pub fn cancel_order(&mut self, id: u64) -> Option<Order> {
+        let price_key = self.order_index.remove(&id)?;
+        let book = match price_key.side {
+            Side::Buy => &mut self.buy_side,
+            Side::Sell => &mut self.sell_side,
+        };
+
+        let orders = book.get_mut(&price_key)?;
+        let pos = orders.iter().position(|o| o.id == id)?;
+        let order = orders.remove(pos);
+
+        if orders.is_empty() {
+            book.remove(&price_key);
+        }
+
+        order
+    }
+
+    /// Amends a resting order's price and/or quantity.
+    ///
+    /// - A pure quantity reduction (same price, smaller quantity) is applied in
+    ///   place, keeping the order's time priority.
+    /// - A price change or quantity increase loses priority: the order is
+    ///   removed and re-queued at the back of the target level.
+    ///
+    /// Returns `true` if an order with the given id was found and amended.
+    /// Only fixed-price orders can be amended; a pegged order's id returns `false`.
+    pub fn amend_order(&mut self, id: u64, new_price: u64, new_qty: u64) -> bool {
+        let price_key = match self.order_index.get(&id) {
+            Some(price_key) => *price_key,
+            None => return false,
+        };
+
+        // Reduction at the same price preserves time priority.
+        if new_price == price_key.price {
+            let book = match price_key.side {
+                Side::Buy => &mut self.buy_side,
+                Side::Sell => &mut self.sell_side,
+            };
+
+            if let Some(orders) = book.get_mut(&price_key) {
+                if let Some(order) = orders.iter_mut().find(|o| o.id == id) {
+                    if new_qty <= order.quantity {
+                        order.quantity = new_qty;
+                        return true;
+                    }
+                }
+            }
+        }
+
+        // Price change or quantity increase re-queues at the back.
+        let side = price_key.side;
+        match self.cancel_order(id) {
+            Some(mut order) => {
+                order.price = new_price;
+                order.quantity = new_qty;
+                order.timestamp = now_ms();
+                self.add_to_book(side, order);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sweeps expired orders from both sides of the book.
+    ///
+    /// Unlike the bounded reaping done during matching, this maintenance call
+    /// removes every order whose `expiry_ts` is at or before `now` and drops any
+    /// price levels left empty. Returns the number of orders removed.
+    pub fn expire_orders(&mut self, now: u128) -> usize {
+        let mut removed_ids = Vec::new();
+
+        for book in [&mut self.buy_side, &mut self.sell_side] {
+            let mut empty_levels = Vec::new();
+            for (key, orders) in book.iter_mut() {
+                orders.retain(|order| {
+                    if order.expiry_ts.is_some_and(|exp| exp <= now) {
+                        removed_ids.push(order.id);
+                        false
+                    } else {
+                        true
+                    }
+                });
+                if orders.is_empty() {
+                    empty_levels.push(*key);
+                }
+            }
+            for key in empty_levels {
+                book.remove(&key);
+            }
+        }
+
+        let count = removed_ids.len();
+        for id in removed_ids {
+            self.order_index.remove(&id);
+        }
+        count
+    }
+
     /// Returns the best available buy price and total quantity at that price.
     ///
-    /// - Best buy = highest bid price.
+    /// - Best buy = highest bid price, considering both fixed and pegged orders.
     /// - If no buy orders exist, returns None.
     pub fn best_buy(&self) -> Option<(u64, u64)> {
-        self.buy_side.first_key_value().map(|(k, orders)| {
-            let total_qty = orders.iter().map(|o| o.quantity).sum();
-            (k.price, total_qty)
-        })
+        self.best_level(Side::Buy)
     }
 
     /// Returns the best available sell price and total quantity at that price.
     ///
-    /// - Best sell = lowest ask price.
+    /// - Best sell = lowest ask price, considering both fixed and pegged orders.
     /// - If no sell orders exist, returns None.
     pub fn best_sell(&self) -> Option<(u64, u64)> {
-        self.sell_side.first_key_value().map(|(k, orders)| {
-            let total_qty = orders.iter().map(|o| o.quantity).sum();
-            (k.price, total_qty)
-        })
+        self.best_level(Side::Sell)
+    }
+
+    /// Returns the best price on `side` and the aggregated quantity resting at
+    /// that effective price across fixed and pegged orders.
+    fn best_level(&self, side: Side) -> Option<(u64, u64)> {
+        let (price, _, _) = self.best_maker(side)?;
+
+        let mut total_qty = 0;
+        if let Some(orders) = self.fixed_book(side).get(&PriceLevel::new(price, side)) {
+            total_qty += orders.iter().map(|o| o.quantity).sum::<u64>();
+        }
+        if let Some(oracle) = self.oracle_price {
+            for pegged in self.pegged(side) {
+                if pegged_price(side, oracle, pegged.offset, pegged.peg_limit) == Some(price) {
+                    total_qty += pegged.order.quantity;
+                }
+            }
+        }
+
+        Some((price, total_qty))
+    }
+
+    /// Returns the top `levels` price levels on `side` as `(price, quantity)`
+    /// pairs in priority order, aggregating the quantity resting at each level.
+    ///
+    /// This is the L2 market-data view. It merges the fixed price levels with the
+    /// effective prices of any currently resolvable pegged orders, so the snapshot
+    /// stays consistent with `best_buy`/`best_sell`. `PriceLevel`'s ordering sorts
+    /// each level by priority; pegged orders that cannot resolve are omitted.
+    pub fn depth(&self, side: Side, levels: usize) -> Vec<(u64, u64)> {
+        let mut totals: BTreeMap<PriceLevel, u64> = BTreeMap::new();
+        for (key, orders) in self.fixed_book(side).iter() {
+            *totals.entry(*key).or_insert(0) += orders.iter().map(|o| o.quantity).sum::<u64>();
+        }
+        if let Some(oracle) = self.oracle_price {
+            for pegged in self.pegged(side) {
+                if let Some(price) = pegged_price(side, oracle, pegged.offset, pegged.peg_limit) {
+                    *totals.entry(PriceLevel::new(price, side)).or_insert(0) += pegged.order.quantity;
+                }
+            }
+        }
+        totals
+            .into_iter()
+            .take(levels)
+            .map(|(key, qty)| (key.price, qty))
+            .collect()
+    }
+
+    /// Returns the midpoint between the best bid and best ask, or `None` unless
+    /// both sides are populated.
+    pub fn mid_price(&self) -> Option<u64> {
+        let (bid, _) = self.best_buy()?;
+        let (ask, _) = self.best_sell()?;
+        Some((bid + ask) / 2)
+    }
+
+    /// Returns the spread between the best ask and best bid, or `None` unless
+    /// both sides are populated.
+    pub fn spread(&self) -> Option<u64> {
+        let (bid, _) = self.best_buy()?;
+        let (ask, _) = self.best_sell()?;
+        Some(ask.saturating_sub(bid))
     }
 
     /// Returns a reference to the orders at the given price level.
@@ -14,7 +14,7 @@ mod tests {
         let mut book = setup();
 
         // when a buy order is placed
-        let trades = book.place_order(Side::Buy, 100, 10, 1);
+        let trades = book.place_order(Side::Buy, 100, 10, 1).unwrap();
         
         // then no trades are returned and the order is on the buy side
         assert!(trades.is_empty());
@@ -26,10 +26,10 @@ mod tests {
     fn test_no_match_buy_order() {
         // given a sell order on the book
         let mut book = setup();
-        book.place_order(Side::Sell, 105, 5, 101);
+        book.place_order(Side::Sell, 105, 5, 101).unwrap();
 
         // when a buy order is placed below the sell price
-        let trades = book.place_order(Side::Buy, 100, 10, 2);
+        let trades = book.place_order(Side::Buy, 100, 10, 2).unwrap();
 
         // then no match occurs, and both orders are on the book
         assert!(trades.is_empty());
@@ -41,10 +41,10 @@ mod tests {
     fn test_no_match_sell_order() {
         // given a buy order on the book
         let mut book = setup();
-        book.place_order(Side::Buy, 95, 5, 101);
+        book.place_order(Side::Buy, 95, 5, 101).unwrap();
 
         // when a sell order is placed above the buy price
-        let trades = book.place_order(Side::Sell, 100, 10, 2);
+        let trades = book.place_order(Side::Sell, 100, 10, 2).unwrap();
 
         // then no match occurs, and both orders are on the book
         assert!(trades.is_empty());
@@ -57,10 +57,10 @@ mod tests {
     fn test_full_taker_fill() {
         // given a sell order of quantity 10
         let mut book = setup();
-        book.place_order(Side::Sell, 100, 10, 101);
+        book.place_order(Side::Sell, 100, 10, 101).unwrap();
 
         // when an incoming buy order of quantity 15 is placed
-        let trades = book.place_order(Side::Buy, 105, 15, 2);
+        let trades = book.place_order(Side::Buy, 105, 15, 2).unwrap();
 
         // then the incoming order is partially filled, the resting order is fully filled and removed
         assert_eq!(trades.len(), 1);
@@ -77,10 +77,10 @@ mod tests {
     fn test_full_maker_fill() {
         // given a sell order of quantity 15
         let mut book = setup();
-        book.place_order(Side::Sell, 100, 15, 101);
+        book.place_order(Side::Sell, 100, 15, 101).unwrap();
 
         // when an incoming buy order of quantity 10 is placed
-        let trades = book.place_order(Side::Buy, 100, 10, 2);
+        let trades = book.place_order(Side::Buy, 100, 10, 2).unwrap();
 
         // then the resting order is partially filled (5 left) and the incoming order is fully filled
         assert_eq!(trades.len(), 1);
@@ -95,10 +95,10 @@ mod tests {
     fn test_partial_fill_buy_matches_one_sell() {
         // given a sell order on the book
         let mut book = setup();
-        book.place_order(Side::Sell, 100, 10, 101);
+        book.place_order(Side::Sell, 100, 10, 101).unwrap();
 
         // when a buy order with smaller quantity is placed
-        let trades = book.place_order(Side::Buy, 100, 5, 2);
+        let trades = book.place_order(Side::Buy, 100, 5, 2).unwrap();
 
         // then a single trade occurs, and the resting sell order's quantity is reduced
         assert_eq!(trades.len(), 1);
@@ -115,10 +115,10 @@ mod tests {
     fn test_partial_fill_sell_matches_one_buy() {
         // given a buy order on the book
         let mut book = setup();
-        book.place_order(Side::Buy, 100, 10, 101);
+        book.place_order(Side::Buy, 100, 10, 101).unwrap();
 
         // when a sell order with smaller quantity is placed
-        let trades = book.place_order(Side::Sell, 100, 5, 2);
+        let trades = book.place_order(Side::Sell, 100, 5, 2).unwrap();
 
         // then a single trade occurs, and the resting buy order's quantity is reduced
         assert_eq!(trades.len(), 1);
@@ -136,11 +136,11 @@ mod tests {
     fn test_buy_price_priority() {
         // given a book with two sell orders at different prices
         let mut book = setup();
-        book.place_order(Side::Sell, 99, 5, 101); // Better price
-        book.place_order(Side::Sell, 100, 5, 102);
+        book.place_order(Side::Sell, 99, 5, 101).unwrap(); // Better price
+        book.place_order(Side::Sell, 100, 5, 102).unwrap();
 
         // when an incoming buy order is placed that can fill both
-        let trades = book.place_order(Side::Buy, 100, 10, 2);
+        let trades = book.place_order(Side::Buy, 100, 10, 2).unwrap();
 
         // then it fills the best price (99) first
         assert_eq!(trades.len(), 2);
@@ -162,11 +162,11 @@ mod tests {
     fn test_sell_price_priority() {
         // given a book with two buy orders at different prices
         let mut book = setup();
-        book.place_order(Side::Buy, 101, 5, 101); // Better price
-        book.place_order(Side::Buy, 100, 5, 102);
+        book.place_order(Side::Buy, 101, 5, 101).unwrap(); // Better price
+        book.place_order(Side::Buy, 100, 5, 102).unwrap();
 
         // when an incoming sell order is placed that can fill both
-        let trades = book.place_order(Side::Sell, 100, 10, 2);
+        let trades = book.place_order(Side::Sell, 100, 10, 2).unwrap();
 
         // then it fills the best price (101) first
         assert_eq!(trades.len(), 2);
@@ -189,11 +189,11 @@ mod tests {
     fn test_time_priority_at_same_price() {
         // given two sell orders at the same price, placed at different times
         let mut book = setup();
-        book.place_order(Side::Sell, 100, 5, 101); // Oldest order
-        book.place_order(Side::Sell, 100, 5, 102); // Newest order
+        book.place_order(Side::Sell, 100, 5, 101).unwrap(); // Oldest order
+        book.place_order(Side::Sell, 100, 5, 102).unwrap(); // Newest order
 
         // when a single incoming buy order of quantity 5 is placed
-        let trades = book.place_order(Side::Buy, 100, 5, 2);
+        let trades = book.place_order(Side::Buy, 100, 5, 2).unwrap();
 
         // then it fills the oldest order (ID 101) first
         assert_eq!(trades.len(), 1);
@@ -212,12 +212,12 @@ mod tests {
     fn test_large_order_spanning_multiple_levels() {
         // given a book with multiple sell orders at different prices
         let mut book = setup();
-        book.place_order(Side::Sell, 100, 5, 101);
-        book.place_order(Side::Sell, 101, 10, 102);
-        book.place_order(Side::Sell, 102, 15, 103);
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+        book.place_order(Side::Sell, 101, 10, 102).unwrap();
+        book.place_order(Side::Sell, 102, 15, 103).unwrap();
 
         // when a large buy order is placed that consumes all orders
-        let trades = book.place_order(Side::Buy, 105, 30, 2);
+        let trades = book.place_order(Side::Buy, 105, 30, 2).unwrap();
 
         // then all resting orders are filled and trades are in price order
         assert_eq!(trades.len(), 3);
@@ -235,7 +235,7 @@ mod tests {
         let mut book = setup();
 
         // when an order with zero quantity is placed
-        let trades = book.place_order(Side::Buy, 100, 0, 1);
+        let trades = book.place_order(Side::Buy, 100, 0, 1).unwrap();
 
         // then no trades occur and the book remains empty
         assert!(trades.is_empty());
@@ -247,11 +247,11 @@ mod tests {
     fn test_market_cross() {
         // given a market where bid > ask
         let mut book = setup();
-        book.place_order(Side::Sell, 99, 10, 101); // Ask
-        book.place_order(Side::Buy, 100, 11, 102); // Bid
+        book.place_order(Side::Sell, 99, 10, 101).unwrap(); // Ask
+        book.place_order(Side::Buy, 100, 11, 102).unwrap(); // Bid
 
         // when a new order is placed (doesn't matter which side)
-        let trades = book.place_order(Side::Sell, 98, 10, 2);
+        let trades = book.place_order(Side::Sell, 98, 10, 2).unwrap();
 
         // then it first matches the best bid at 100
         assert_eq!(trades.len(), 1);
@@ -259,4 +259,515 @@ mod tests {
         assert_eq!(trades[0].maker_id, 102);
         assert_eq!(trades[0].taker_id, 2);
     }
+
+    #[test]
+    fn test_cancel_resting_order() {
+        // given two resting sell orders at the same price
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+        book.place_order(Side::Sell, 100, 7, 102).unwrap();
+
+        // when the first order is cancelled
+        let cancelled = book.cancel_order(101);
+
+        // then it is returned and only the second order remains
+        assert!(cancelled.is_some());
+        assert_eq!(cancelled.unwrap().id, 101);
+        assert_eq!(book.best_sell(), Some((100, 7)));
+
+        let remaining = book.get_orders(&PriceLevel::new(100, Side::Sell)).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, 102);
+    }
+
+    #[test]
+    fn test_cancel_drops_empty_level() {
+        // given a single resting buy order
+        let mut book = setup();
+        book.place_order(Side::Buy, 100, 5, 101).unwrap();
+
+        // when it is cancelled
+        let cancelled = book.cancel_order(101);
+
+        // then the price level is gone entirely
+        assert!(cancelled.is_some());
+        assert_eq!(book.best_buy(), None);
+        assert!(book.is_buy_side_empty());
+    }
+
+    #[test]
+    fn test_cancel_unknown_order() {
+        // given an empty book
+        let mut book = setup();
+
+        // when an unknown id is cancelled, nothing is returned
+        assert!(book.cancel_order(999).is_none());
+    }
+
+    #[test]
+    fn test_amend_reduce_keeps_priority() {
+        // given two sell orders at the same price
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 10, 101).unwrap(); // oldest
+        book.place_order(Side::Sell, 100, 5, 102).unwrap();
+
+        // when the oldest is reduced in quantity
+        assert!(book.amend_order(101, 100, 4));
+
+        // then it keeps its place at the front of the queue
+        let orders = book.get_orders(&PriceLevel::new(100, Side::Sell)).unwrap();
+        assert_eq!(orders[0].id, 101);
+        assert_eq!(orders[0].quantity, 4);
+        assert_eq!(orders[1].id, 102);
+    }
+
+    #[test]
+    fn test_amend_increase_loses_priority() {
+        // given two sell orders at the same price
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap(); // oldest
+        book.place_order(Side::Sell, 100, 5, 102).unwrap();
+
+        // when the oldest increases its quantity
+        assert!(book.amend_order(101, 100, 8));
+
+        // then it is re-queued behind the other order
+        let orders = book.get_orders(&PriceLevel::new(100, Side::Sell)).unwrap();
+        assert_eq!(orders[0].id, 102);
+        assert_eq!(orders[1].id, 101);
+        assert_eq!(orders[1].quantity, 8);
+    }
+
+    #[test]
+    fn test_amend_price_moves_level() {
+        // given a resting buy order
+        let mut book = setup();
+        book.place_order(Side::Buy, 100, 5, 101).unwrap();
+
+        // when its price is amended upwards
+        assert!(book.amend_order(101, 102, 5));
+
+        // then it rests at the new level and the old one is empty
+        assert_eq!(book.best_buy(), Some((102, 5)));
+        assert!(book.get_orders(&PriceLevel::new(100, Side::Buy)).is_none());
+    }
+
+    #[test]
+    fn test_market_buy_does_not_rest() {
+        // given two sell levels
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+        book.place_order(Side::Sell, 101, 5, 102).unwrap();
+
+        // when a market buy larger than available is placed
+        let trades = book.place(Side::Buy, 0, 12, 2, OrderType::Market, TimeInForce::Gtc).unwrap();
+
+        // then it sweeps both levels and discards the unfilled remainder
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].price, 100);
+        assert_eq!(trades[1].price, 101);
+        assert!(book.is_sell_side_empty());
+        assert!(book.is_buy_side_empty());
+    }
+
+    #[test]
+    fn test_post_only_rejected_on_cross() {
+        // given a resting sell order
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+
+        // when a post-only buy that would cross is placed
+        let trades = book.place(Side::Buy, 100, 5, 2, OrderType::PostOnly, TimeInForce::Gtc).unwrap();
+
+        // then it is rejected: no trades, nothing rested
+        assert!(trades.is_empty());
+        assert_eq!(book.best_sell(), Some((100, 5)));
+        assert_eq!(book.best_buy(), None);
+    }
+
+    #[test]
+    fn test_post_only_rests_when_not_crossing() {
+        // given a resting sell order
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+
+        // when a post-only buy below the ask is placed
+        let trades = book.place(Side::Buy, 99, 5, 2, OrderType::PostOnly, TimeInForce::Gtc).unwrap();
+
+        // then it rests as a maker
+        assert!(trades.is_empty());
+        assert_eq!(book.best_buy(), Some((99, 5)));
+    }
+
+    #[test]
+    fn test_post_only_slide_reprices_inside_best() {
+        // given a resting sell order at 100
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+
+        // when a crossing post-only-slide buy is placed
+        let trades = book.place(Side::Buy, 100, 5, 2, OrderType::PostOnlySlide, TimeInForce::Gtc).unwrap();
+
+        // then it rests one tick inside the ask rather than crossing
+        assert!(trades.is_empty());
+        assert_eq!(book.best_buy(), Some((99, 5)));
+        assert_eq!(book.best_sell(), Some((100, 5)));
+    }
+
+    #[test]
+    fn test_rejects_off_tick_price() {
+        // given a book with a tick size of 5
+        let mut book = OrderBook::new(5, 1, 1);
+
+        // when a price off the tick grid is submitted
+        let result = book.place_order(Side::Buy, 102, 10, 1);
+
+        // then it is rejected and nothing rests
+        assert_eq!(result, Err(OrderError::InvalidTick));
+        assert_eq!(book.best_buy(), None);
+    }
+
+    #[test]
+    fn test_rejects_off_lot_quantity() {
+        // given a book with a lot size of 10
+        let mut book = OrderBook::new(1, 10, 1);
+
+        // when a quantity off the lot grid is submitted
+        let result = book.place_order(Side::Buy, 100, 15, 1);
+
+        // then it is rejected
+        assert_eq!(result, Err(OrderError::InvalidLot));
+    }
+
+    #[test]
+    fn test_rejects_below_minimum() {
+        // given a book with a minimum size of 5
+        let mut book = OrderBook::new(1, 1, 5);
+
+        // when a quantity below the minimum is submitted
+        let result = book.place_order(Side::Buy, 100, 3, 1);
+
+        // then it is rejected
+        assert_eq!(result, Err(OrderError::BelowMinimum));
+    }
+
+    #[test]
+    fn test_accepts_on_grid_order() {
+        // given a constrained book
+        let mut book = OrderBook::new(5, 10, 10);
+
+        // when an order on the grid and at/above the minimum is submitted
+        let trades = book.place_order(Side::Buy, 105, 20, 1).unwrap();
+
+        // then it is accepted and rests
+        assert!(trades.is_empty());
+        assert_eq!(book.best_buy(), Some((105, 20)));
+    }
+
+    #[test]
+    fn test_ioc_discards_remainder() {
+        // given a single resting sell order
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+
+        // when an IOC buy larger than available is placed
+        let trades = book
+            .place(Side::Buy, 100, 10, 2, OrderType::Limit, TimeInForce::Ioc)
+            .unwrap();
+
+        // then it fills what it can and discards the rest without resting
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].quantity, 5);
+        assert_eq!(book.best_buy(), None);
+        assert!(book.is_sell_side_empty());
+    }
+
+    #[test]
+    fn test_gtd_expired_maker_reaped_not_traded() {
+        // given a resting sell order that has already expired (expiry in the past)
+        let mut book = setup();
+        book.place(Side::Sell, 100, 5, 101, OrderType::Limit, TimeInForce::Gtd(1))
+            .unwrap();
+
+        // when a crossing buy arrives
+        let trades = book.place_order(Side::Buy, 100, 5, 2).unwrap();
+
+        // then the expired maker is reaped without producing a trade, and the
+        // incoming order rests
+        assert!(trades.is_empty());
+        assert_eq!(book.best_sell(), None);
+        assert_eq!(book.best_buy(), Some((100, 5)));
+    }
+
+    #[test]
+    fn test_gtd_reaping_is_bounded_per_call() {
+        // given six already-expired sell orders at the same level
+        let mut book = setup();
+        for id in 101..=106 {
+            book.place(Side::Sell, 100, 1, id, OrderType::Limit, TimeInForce::Gtd(1))
+                .unwrap();
+        }
+
+        // when a crossing buy arrives
+        let trades = book.place_order(Side::Buy, 100, 3, 2).unwrap();
+
+        // then at most DROP_EXPIRED_ORDER_LIMIT (5) are reaped; the sixth blocks
+        // further matching and the taker rests instead of trading
+        assert!(trades.is_empty());
+        assert_eq!(book.best_sell(), Some((100, 1)));
+        assert_eq!(book.best_buy(), Some((100, 3)));
+    }
+
+    #[test]
+    fn test_expire_orders_sweeps_all_sides() {
+        // given expired orders resting on both sides plus one live order
+        let mut book = setup();
+        book.place(Side::Buy, 90, 5, 101, OrderType::Limit, TimeInForce::Gtd(1))
+            .unwrap();
+        book.place(Side::Sell, 110, 5, 102, OrderType::Limit, TimeInForce::Gtd(1))
+            .unwrap();
+        book.place_order(Side::Buy, 95, 5, 103).unwrap(); // GTC, never expires
+
+        // when a maintenance sweep runs past the expiry
+        let removed = book.expire_orders(2);
+
+        // then both expired orders are gone and the live one remains
+        assert_eq!(removed, 2);
+        assert_eq!(book.best_buy(), Some((95, 5)));
+        assert_eq!(book.best_sell(), None);
+    }
+
+    #[test]
+    fn test_pegged_buy_takes_fixed_sell() {
+        // given a resting sell and an oracle price
+        let mut book = setup();
+        book.place_order(Side::Sell, 100, 5, 101).unwrap();
+        book.set_oracle_price(100);
+
+        // when a pegged buy at the oracle is placed
+        let trades = book.place_pegged_order(Side::Buy, 0, 5, 201, None).unwrap();
+
+        // then it trades against the fixed sell at its price
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].price, 100);
+        assert_eq!(trades[0].maker_id, 101);
+        assert_eq!(trades[0].taker_id, 201);
+        assert!(book.is_sell_side_empty());
+        assert_eq!(book.best_buy(), None);
+    }
+
+    #[test]
+    fn test_pegged_sell_rests_and_is_taken() {
+        // given an oracle price and a pegged sell one tick below it
+        let mut book = setup();
+        book.set_oracle_price(100);
+        book.place_pegged_order(Side::Sell, -1, 5, 201, None).unwrap();
+
+        // then it shows as the best ask at its effective price
+        assert_eq!(book.best_sell(), Some((99, 5)));
+
+        // when a crossing buy arrives it trades at the effective price
+        let trades = book.place_order(Side::Buy, 100, 5, 2).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].price, 99);
+        assert_eq!(trades[0].maker_id, 201);
+        assert_eq!(trades[0].taker_id, 2);
+        assert_eq!(book.best_sell(), None);
+    }
+
+    #[test]
+    fn test_pegged_order_reprices_with_oracle() {
+        // given a resting pegged sell tracking the oracle exactly
+        let mut book = setup();
+        book.set_oracle_price(100);
+        book.place_pegged_order(Side::Sell, 0, 5, 201, None).unwrap();
+        assert_eq!(book.best_sell(), Some((100, 5)));
+
+        // when the oracle moves, the effective price follows
+        book.set_oracle_price(90);
+        assert_eq!(book.best_sell(), Some((90, 5)));
+    }
+
+    #[test]
+    fn test_pegged_buy_skipped_when_peg_limit_breached() {
+        // given a pegged buy whose peg would exceed its worst-case limit
+        let mut book = setup();
+        book.set_oracle_price(100);
+        book.place_pegged_order(Side::Buy, 0, 5, 201, Some(95)).unwrap();
+
+        // then it is temporarily invalid: not shown, not matched, not removed
+        assert_eq!(book.best_buy(), None);
+
+        // when the oracle moves back within range, it resolves and participates
+        book.set_oracle_price(95);
+        assert_eq!(book.best_buy(), Some((95, 5)));
+    }
+
+    #[test]
+    fn test_pegged_order_invalid_until_peg_resolves() {
+        // given a pegged buy whose effective price is currently non-positive
+        let mut book = setup();
+        book.set_oracle_price(100);
+        book.place_pegged_order(Side::Buy, -200, 5, 201, None).unwrap();
+
+        // then it is temporarily invalid: not shown, not matched, not removed
+        assert_eq!(book.best_buy(), None);
+
+        // when the oracle rises enough to resolve the peg, it participates again
+        book.set_oracle_price(250);
+        assert_eq!(book.best_buy(), Some((50, 5)));
+    }
+
+    #[test]
+    fn test_matching_merges_fixed_and_pegged_priority() {
+        // given a fixed sell at 101 and a better pegged sell at 100
+        let mut book = setup();
+        book.set_oracle_price(100);
+        book.place_order(Side::Sell, 101, 5, 101).unwrap();
+        book.place_pegged_order(Side::Sell, 0, 5, 201, None).unwrap();
+
+        // when a buy sweeps both
+        let trades = book.place_order(Side::Buy, 101, 10, 2).unwrap();
+
+        // then the better-priced pegged maker fills first, then the fixed one
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].price, 100);
+        assert_eq!(trades[0].maker_id, 201);
+        assert_eq!(trades[1].price, 101);
+        assert_eq!(trades[1].maker_id, 101);
+    }
+
+    #[test]
+    fn test_stp_allow_self_trades() {
+        // given a resting sell owned by account 7
+        let mut book = setup();
+        book.place_full(Side::Sell, 100, 5, 101, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::Allow)
+            .unwrap();
+
+        // when the same account crosses it with prevention disabled
+        let trades = book
+            .place_full(Side::Buy, 100, 5, 2, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::Allow)
+            .unwrap();
+
+        // then the self-match trades as usual
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].maker_id, 101);
+    }
+
+    #[test]
+    fn test_stp_cancel_resting_skips_own_maker() {
+        // given two sells at the same level owned by accounts 7 and 9
+        let mut book = setup();
+        book.place_full(Side::Sell, 100, 5, 101, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::Allow)
+            .unwrap();
+        book.place_full(Side::Sell, 100, 5, 102, 9, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::Allow)
+            .unwrap();
+
+        // when account 7 crosses with CancelResting
+        let trades = book
+            .place_full(Side::Buy, 100, 10, 2, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::CancelResting)
+            .unwrap();
+
+        // then its own maker is discarded and only the other account's trades
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].maker_id, 102);
+        assert_eq!(trades[0].quantity, 5);
+        assert_eq!(book.best_sell(), None);
+        assert_eq!(book.best_buy(), Some((100, 5)));
+    }
+
+    #[test]
+    fn test_stp_cancel_taker_drops_remainder() {
+        // given a sell from account 9 ahead of one from account 7
+        let mut book = setup();
+        book.place_full(Side::Sell, 100, 5, 101, 9, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::Allow)
+            .unwrap();
+        book.place_full(Side::Sell, 100, 5, 102, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::Allow)
+            .unwrap();
+
+        // when account 7 crosses with CancelTaker
+        let trades = book
+            .place_full(Side::Buy, 100, 10, 2, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::CancelTaker)
+            .unwrap();
+
+        // then it trades the other account's maker, then stops and drops its own
+        // remainder without resting
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].maker_id, 101);
+        assert_eq!(book.best_sell(), Some((100, 5)));
+        assert_eq!(book.best_buy(), None);
+    }
+
+    #[test]
+    fn test_stp_decrement_and_cancel() {
+        // given a resting sell owned by account 7
+        let mut book = setup();
+        book.place_full(Side::Sell, 100, 5, 101, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::Allow)
+            .unwrap();
+
+        // when account 7 crosses with a larger DecrementAndCancel order
+        let trades = book
+            .place_full(Side::Buy, 100, 8, 2, 7, OrderType::Limit, TimeInForce::Gtc, SelfTradePrevention::DecrementAndCancel)
+            .unwrap();
+
+        // then the overlap (5) is cancelled off both without a trade, and the
+        // taker's remainder (3) rests
+        assert!(trades.is_empty());
+        assert_eq!(book.best_sell(), None);
+        assert_eq!(book.best_buy(), Some((100, 3)));
+    }
+
+    #[test]
+    fn test_depth_aggregates_levels_in_priority_order() {
+        // given several buys resting across three price levels
+        let mut book = setup();
+        book.place(Side::Buy, 100, 5, 1, OrderType::Limit, TimeInForce::Gtc).unwrap();
+        book.place(Side::Buy, 100, 3, 2, OrderType::Limit, TimeInForce::Gtc).unwrap();
+        book.place(Side::Buy, 98, 4, 3, OrderType::Limit, TimeInForce::Gtc).unwrap();
+        book.place(Side::Buy, 99, 7, 4, OrderType::Limit, TimeInForce::Gtc).unwrap();
+
+        // when requesting the top two levels of depth
+        let depth = book.depth(Side::Buy, 2);
+
+        // then the highest-priced levels come first with quantities summed
+        assert_eq!(depth, vec![(100, 8), (99, 7)]);
+    }
+
+    #[test]
+    fn test_depth_sell_side_and_clamped_count() {
+        // given two sell levels and a request for more levels than exist
+        let mut book = setup();
+        book.place(Side::Sell, 101, 2, 1, OrderType::Limit, TimeInForce::Gtc).unwrap();
+        book.place(Side::Sell, 100, 6, 2, OrderType::Limit, TimeInForce::Gtc).unwrap();
+
+        // when requesting more levels than are populated
+        let depth = book.depth(Side::Sell, 10);
+
+        // then it returns every level, lowest ask first
+        assert_eq!(depth, vec![(100, 6), (101, 2)]);
+    }
+
+    #[test]
+    fn test_mid_price_and_spread() {
+        // given a two-sided book
+        let mut book = setup();
+        book.place(Side::Buy, 98, 5, 1, OrderType::Limit, TimeInForce::Gtc).unwrap();
+        book.place(Side::Sell, 102, 5, 2, OrderType::Limit, TimeInForce::Gtc).unwrap();
+
+        // then the midpoint and spread are derived from the two bests
+        assert_eq!(book.mid_price(), Some(100));
+        assert_eq!(book.spread(), Some(4));
+    }
+
+    #[test]
+    fn test_mid_price_and_spread_require_both_sides() {
+        // given a book with only a bid
+        let mut book = setup();
+        book.place(Side::Buy, 98, 5, 1, OrderType::Limit, TimeInForce::Gtc).unwrap();
+
+        // then neither midpoint nor spread can be computed
+        assert_eq!(book.mid_price(), None);
+        assert_eq!(book.spread(), None);
+        assert!(book.depth(Side::Sell, 5).is_empty());
+    }
 }